@@ -0,0 +1,64 @@
+//! Parser output: a syntax tree that mirrors source structure closely and
+//! has not yet been resolved or type-checked. [`crate::hir`] is what this
+//! lowers to once names are resolved and types are inferred.
+
+use crate::diagnostics::Span;
+use crate::hir::Ty;
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Var {
+        name: String,
+        span: Span,
+    },
+    Literal {
+        span: Span,
+    },
+    Field {
+        base: Box<Expr>,
+        field: String,
+        span: Span,
+    },
+    Borrow {
+        mutable: bool,
+        inner: Box<Expr>,
+        span: Span,
+    },
+    Call {
+        callee: String,
+        args: Vec<Expr>,
+        span: Span,
+    },
+    StructLiteral {
+        ty_name: String,
+        fields: Vec<(String, Expr)>,
+        span: Span,
+    },
+    /// `expr?`, produced by [`crate::parser::parse_postfix`]. Lowered to
+    /// [`crate::hir::Expr::Try`] once the type checker has confirmed the
+    /// enclosing function is allowed to propagate the error this way.
+    Try {
+        inner: Box<Expr>,
+        span: Span,
+    },
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Var { span, .. }
+            | Expr::Literal { span }
+            | Expr::Field { span, .. }
+            | Expr::Borrow { span, .. }
+            | Expr::Call { span, .. }
+            | Expr::StructLiteral { span, .. }
+            | Expr::Try { span, .. } => *span,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FuncDecl {
+    pub name: String,
+    pub ret: Ty,
+}
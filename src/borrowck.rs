@@ -0,0 +1,733 @@
+//! Ownership and borrow-checking pass.
+//!
+//! Runs over [`hir::Function`] bodies once name resolution and type
+//! inference have run, and enforces the aliasing rules implied by `&T` /
+//! `&mut T` parameters and receivers: any number of shared borrows of a place
+//! may coexist, a mutable borrow must be exclusive, and a moved-from place
+//! cannot be used again.
+//!
+//! This is a dataflow pass, not a full MIR-style borrow checker: it walks
+//! each function body once in control-flow order, threading a flat map from
+//! *place path* (`c`, `c.radius`, ...) to [`PlaceState`] through statements,
+//! pushing/popping a lexical scope stack as blocks open and close, and a
+//! parallel stack of borrows scoped to the binding that holds them.
+
+use std::collections::HashMap;
+
+use crate::diagnostics::{Diagnostic, DiagnosticBag, Span};
+use crate::hir::{Block, Expr, Function, Pattern, Stmt, WhenArm};
+use crate::trace::{self, Tracer};
+
+#[derive(Debug, Clone)]
+enum PlaceState {
+    Owned,
+    Moved { at: Span },
+    Shared { sites: Vec<Span> },
+    Mutable { at: Span },
+}
+
+/// A borrow recorded against a place, released when `scope_depth` pops (if
+/// bound to a `let`) or immediately at the end of the current statement (if
+/// taken as a bare argument, e.g. `f(&x)`).
+struct ActiveBorrow {
+    path: String,
+    scope_depth: usize,
+    temporary: bool,
+}
+
+struct Checker<'a> {
+    bag: &'a mut DiagnosticBag,
+    /// Flat map keyed by dotted place path (`"c"`, `"c.radius"`).
+    states: HashMap<String, PlaceState>,
+    /// Variable names declared at each scope depth, so a block's locals can
+    /// be forgotten (and freed of their borrows) when it closes.
+    scope_locals: Vec<Vec<String>>,
+    active_borrows: Vec<ActiveBorrow>,
+}
+
+pub fn check_function(func: &Function, bag: &mut DiagnosticBag, tracer: &Tracer) {
+    let _span = tracer.span("borrowck", func.name.clone(), &[trace::field("function", &func.name)]);
+    let mut checker = Checker {
+        bag,
+        states: HashMap::new(),
+        scope_locals: vec![Vec::new()],
+        active_borrows: Vec::new(),
+    };
+    for param in &func.params {
+        checker.declare(param.name.clone());
+    }
+    checker.check_block(&func.body);
+    checker.pop_scope();
+}
+
+fn join(path: &[&str]) -> String {
+    path.join(".")
+}
+
+impl<'a> Checker<'a> {
+    fn depth(&self) -> usize {
+        self.scope_locals.len() - 1
+    }
+
+    fn push_scope(&mut self) {
+        self.scope_locals.push(Vec::new());
+    }
+
+    fn pop_scope(&mut self) {
+        let depth = self.depth();
+        let locals = self.scope_locals.pop().unwrap_or_default();
+        let (kept, released): (Vec<_>, Vec<_>) = std::mem::take(&mut self.active_borrows)
+            .into_iter()
+            .partition(|b| b.scope_depth < depth);
+        self.active_borrows = kept;
+        for borrow in released {
+            let borrowed = matches!(
+                self.states.get(&borrow.path),
+                Some(PlaceState::Shared { .. } | PlaceState::Mutable { .. })
+            );
+            if borrowed && !self.active_borrows.iter().any(|b| b.path == borrow.path) {
+                self.states.insert(borrow.path, PlaceState::Owned);
+            }
+        }
+        for name in locals {
+            self.states.retain(|path, _| path != &name && !path.starts_with(&format!("{name}.")));
+        }
+    }
+
+    fn declare(&mut self, name: String) {
+        self.states.insert(name.clone(), PlaceState::Owned);
+        self.scope_locals.last_mut().unwrap().push(name);
+    }
+
+    /// Places whose path overlaps `path` (an ancestor, descendant, or exact
+    /// match), whatever their state — either can invalidate the other, since they alias the same
+    /// storage (e.g. borrowing `c` as a whole conflicts with a live borrow
+    /// of `c.radius`, and vice versa).
+    fn overlapping<'s>(&'s self, path: &str) -> Vec<(&'s String, &'s PlaceState)> {
+        self.states
+            .iter()
+            .filter(|(p, _)| {
+                p.as_str() == path
+                    || p.starts_with(&format!("{path}."))
+                    || path.starts_with(&format!("{p}."))
+            })
+            .collect()
+    }
+
+    /// An ancestor of `path` (itself included) that is `Moved`; if several
+    /// are, which one is returned is not defined —
+    /// moving `c` invalidates `c.radius` just as much as moving `c.radius`
+    /// itself would, since the whole place was given up.
+    fn moved_ancestor(&self, path: &str) -> Option<(String, Span)> {
+        self.overlapping(path).into_iter().find_map(|(p, s)| {
+            let is_ancestor = p.as_str() == path || path.starts_with(&format!("{p}."));
+            match (is_ancestor, s) {
+                (true, PlaceState::Moved { at }) => Some((p.clone(), *at)),
+                _ => None,
+            }
+        })
+    }
+
+    fn check_move(&mut self, path_parts: &[&str], span: Span) {
+        let path = join(path_parts);
+        if let Some((moved_path, at)) = self.moved_ancestor(&path) {
+            self.bag.push(
+                Diagnostic::error(span, format!("use of moved value `{path}`"))
+                    .with_label(at, format!("`{moved_path}` was moved here")),
+            );
+            return;
+        }
+        let conflict = self.overlapping(&path).into_iter().find_map(|(p, s)| match s {
+            PlaceState::Shared { sites } => Some((p.clone(), sites[0], "borrowed")),
+            PlaceState::Mutable { at } => Some((p.clone(), *at, "mutably borrowed")),
+            _ => None,
+        });
+        if let Some((other, at, how)) = conflict {
+            self.bag.push(
+                Diagnostic::error(
+                    span,
+                    format!("cannot move `{path}` because `{other}` is still {how}"),
+                )
+                .with_label(at, format!("`{other}` {how} here")),
+            );
+        }
+        self.states.insert(path, PlaceState::Moved { at: span });
+    }
+
+    fn check_borrow(&mut self, path_parts: &[&str], mutable: bool, span: Span, temporary: bool) {
+        let path = join(path_parts);
+        if let Some((moved_path, at)) = self.moved_ancestor(&path) {
+            self.bag.push(
+                Diagnostic::error(span, format!("use of moved value `{path}`"))
+                    .with_label(at, format!("`{moved_path}` was moved here")),
+            );
+            return;
+        }
+        let conflict = self.overlapping(&path).into_iter().find_map(|(p, s)| match s {
+            PlaceState::Mutable { at } => Some((p.clone(), *at, "mutably borrowed")),
+            PlaceState::Shared { sites } if mutable => Some((p.clone(), sites[0], "borrowed")),
+            _ => None,
+        });
+        if let Some((other, at, how)) = conflict {
+            let kind = if mutable { "mutably borrow" } else { "borrow" };
+            self.bag.push(
+                Diagnostic::error(span, format!("cannot {kind} `{path}` because `{other}` is already {how}"))
+                    .with_label(at, format!("`{other}` {how} here")),
+            );
+        }
+        if mutable {
+            self.states.insert(path.clone(), PlaceState::Mutable { at: span });
+        } else {
+            match self.states.entry(path.clone()) {
+                std::collections::hash_map::Entry::Occupied(mut e) => {
+                    if let PlaceState::Shared { sites } = e.get_mut() {
+                        sites.push(span);
+                    } else {
+                        e.insert(PlaceState::Shared { sites: vec![span] });
+                    }
+                }
+                std::collections::hash_map::Entry::Vacant(e) => {
+                    e.insert(PlaceState::Shared { sites: vec![span] });
+                }
+            }
+        }
+        self.active_borrows.push(ActiveBorrow {
+            path,
+            scope_depth: self.depth(),
+            temporary,
+        });
+    }
+
+    /// Visits a use of `expr` that does not itself move the place it names
+    /// (e.g. the receiver of `.field`, or a condition), flagging
+    /// use-after-move. A `&`/`&mut` expression here still takes its borrow,
+    /// and struct-literal field initializers are still moved into the new
+    /// value.
+    fn check_read(&mut self, expr: &Expr) {
+        if let Expr::Borrow { .. } = expr {
+            self.check_rvalue(expr, false);
+            return;
+        }
+        if let Some(path) = expr.place_path() {
+            let joined = join(&path);
+            if let Some((moved_path, at)) = self.moved_ancestor(&joined) {
+                self.bag.push(
+                    Diagnostic::error(expr.span(), format!("use of moved value `{joined}`"))
+                        .with_label(at, format!("`{moved_path}` was moved here")),
+                );
+            }
+        }
+        match expr {
+            Expr::Field { base, .. } => self.check_read(base),
+            Expr::Call { args, .. } => {
+                for arg in args {
+                    self.check_read(arg);
+                }
+            }
+            Expr::StructLiteral { fields, .. } => {
+                for (_, value) in fields {
+                    self.check_rvalue(value, false);
+                }
+            }
+            Expr::Try { inner, .. } => self.check_read(inner),
+            Expr::Ternary {
+                cond,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.check_read(cond);
+                self.check_read(then_branch);
+                self.check_read(else_branch);
+            }
+            Expr::Binary { lhs, rhs, .. } => {
+                self.check_read(lhs);
+                self.check_read(rhs);
+            }
+            Expr::Index { base, index, .. } => {
+                self.check_read(base);
+                self.check_read(index);
+            }
+            Expr::ArrayLiteral { elements, .. } => {
+                for element in elements {
+                    self.check_read(element);
+                }
+            }
+            Expr::Var { .. } | Expr::Literal { .. } | Expr::Const { .. } | Expr::Borrow { .. } | Expr::EnumLiteral { .. } => {}
+        }
+    }
+
+    /// Visits `expr` in value-producing position: a bare place is a move,
+    /// `&expr`/`&mut expr` takes a borrow, released at the end of the
+    /// statement when `temporary` is set.
+    fn check_rvalue(&mut self, expr: &Expr, temporary: bool) {
+        match expr {
+            Expr::Borrow { mutable, inner, span } => {
+                if let Some(path) = inner.place_path() {
+                    self.check_borrow(&path, *mutable, *span, temporary);
+                } else {
+                    self.check_read(inner);
+                }
+            }
+            Expr::Var { .. } | Expr::Field { .. } => {
+                if let Some(path) = expr.place_path() {
+                    self.check_move(&path, expr.span());
+                } else {
+                    self.check_read(expr);
+                }
+            }
+            Expr::StructLiteral { fields, .. } => {
+                // Each field initializer is a value move/borrow in its own
+                // right — `.radius = c.radius` moves `c.radius`, `.ptr = &c`
+                // lets the borrow escape into the new struct's lifetime.
+                for (_, value) in fields {
+                    self.check_rvalue(value, false);
+                }
+            }
+            Expr::Call { args, .. } => {
+                // A borrow written directly as a call argument (`f(&mut x)`)
+                // is never bound to anything longer-lived than the call
+                // itself, regardless of whether the call's *result* is
+                // bound via `let` — so it's always temporary here.
+                for arg in args {
+                    self.check_rvalue(arg, true);
+                }
+            }
+            Expr::Try { inner, .. } => self.check_rvalue(inner, false),
+            Expr::Ternary {
+                cond,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.check_read(cond);
+                // Only one branch runs, so merge the same way `when` arms
+                // do: a place moved in either branch counts as moved afterwards.
+                let pre = self.states.clone();
+                self.check_rvalue(then_branch, false);
+                let after_then = std::mem::replace(&mut self.states, pre);
+                self.check_rvalue(else_branch, false);
+                for (path, state) in after_then {
+                    if let PlaceState::Moved { at } = state {
+                        if !matches!(self.states.get(&path), Some(PlaceState::Moved { .. })) {
+                            self.states.insert(path, PlaceState::Moved { at });
+                        }
+                    }
+                }
+            }
+            Expr::Binary { lhs, rhs, .. } => {
+                self.check_read(lhs);
+                self.check_read(rhs);
+            }
+            Expr::Index { base, index, .. } => {
+                self.check_read(base);
+                self.check_read(index);
+            }
+            Expr::ArrayLiteral { elements, .. } => {
+                for element in elements {
+                    self.check_rvalue(element, false);
+                }
+            }
+            Expr::Literal { .. } | Expr::Const { .. } | Expr::EnumLiteral { .. } => {}
+        }
+    }
+
+    fn check_block(&mut self, block: &Block) {
+        self.push_scope();
+        for stmt in &block.stmts {
+            self.check_stmt(stmt);
+            self.release_temporary_borrows();
+        }
+        self.pop_scope();
+    }
+
+    /// Releases borrows taken as a bare call argument (`f(&x)`) rather than
+    /// bound with `let` — their lease ends at the statement that took them,
+    /// not at the end of the enclosing block.
+    fn release_temporary_borrows(&mut self) {
+        let depth = self.depth();
+        let mut i = 0;
+        while i < self.active_borrows.len() {
+            if self.active_borrows[i].temporary && self.active_borrows[i].scope_depth == depth {
+                let borrow = self.active_borrows.remove(i);
+                // A `let`-bound borrow of the same place keeps it borrowed.
+                if !self.active_borrows.iter().any(|b| b.path == borrow.path) {
+                    self.states.insert(borrow.path, PlaceState::Owned);
+                }
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    fn check_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Let { name, init, .. } => {
+                self.check_rvalue(init, false);
+                self.declare(name.clone());
+            }
+            Stmt::Assign { target, value, span } => {
+                self.check_rvalue(value, false);
+                if let Some(path) = target.place_path() {
+                    let joined = join(&path);
+                    let conflict = self.overlapping(&joined).into_iter().find_map(|(p, s)| match s {
+                        PlaceState::Shared { sites } => Some((p.clone(), sites[0], "borrowed")),
+                        PlaceState::Mutable { at } => Some((p.clone(), *at, "mutably borrowed")),
+                        _ => None,
+                    });
+                    if let Some((other, at, how)) = conflict {
+                        self.bag.push(
+                            Diagnostic::error(
+                                *span,
+                                format!("cannot assign to `{joined}` because `{other}` is still {how}"),
+                            )
+                            .with_label(at, format!("`{other}` {how} here")),
+                        );
+                    }
+                    // A whole-place reassignment re-initializes everything
+                    // under it too — an old `c.radius -> Moved` entry must
+                    // not survive `c = other;`, or a later read of `c.radius`
+                    // is rejected as still-moved against a place that no
+                    // longer exists.
+                    self.states
+                        .retain(|p, _| p != &joined && !p.starts_with(&format!("{joined}.")));
+                    self.states.insert(joined, PlaceState::Owned);
+                }
+            }
+            Stmt::Expr(expr) => self.check_rvalue(expr, true),
+            Stmt::While { cond, body, .. } => {
+                self.check_read(cond);
+                // Two passes approximate the back-edge: state after one
+                // iteration is fed into a second walk so a move/borrow that
+                // is fine on first entry but stale on re-entry is caught.
+                // The second pass re-walks the same statements, so dedupe
+                // anything it reports that the first pass already did.
+                let first_pass_start = self.bag.len();
+                self.check_block(body);
+                let first_pass_end = self.bag.len();
+                self.check_block(body);
+                self.bag.dedupe_repeated_walk(first_pass_start..first_pass_end);
+            }
+            Stmt::For { binder, index, iter, body, .. } => {
+                self.check_rvalue(iter, false);
+                self.push_scope();
+                self.declare(index.clone());
+                self.declare(binder.clone());
+                // Two passes approximate the back-edge, same as `while`.
+                let first_pass_start = self.bag.len();
+                self.check_block(body);
+                let first_pass_end = self.bag.len();
+                self.check_block(body);
+                self.bag.dedupe_repeated_walk(first_pass_start..first_pass_end);
+                self.pop_scope();
+            }
+            Stmt::When { scrutinee, arms, .. } => {
+                self.check_read(scrutinee);
+                let pre = self.states.clone();
+                let mut any_moved: HashMap<String, Span> = HashMap::new();
+                for arm in arms {
+                    self.states = pre.clone();
+                    self.check_arm(arm);
+                    for (path, state) in &self.states {
+                        if let PlaceState::Moved { at } = state {
+                            any_moved.entry(path.clone()).or_insert(*at);
+                        }
+                    }
+                }
+                self.states = pre;
+                for (path, at) in any_moved {
+                    self.states.insert(path, PlaceState::Moved { at });
+                }
+            }
+            Stmt::Return { value, .. } => {
+                if let Some(value) = value {
+                    self.check_rvalue(value, false);
+                }
+            }
+        }
+    }
+
+    fn check_arm(&mut self, arm: &WhenArm) {
+        self.push_scope();
+        if let Pattern::Binding(name) = &arm.pattern {
+            self.declare(name.clone());
+        }
+        for stmt in &arm.body.stmts {
+            self.check_stmt(stmt);
+            self.release_temporary_borrows();
+        }
+        self.pop_scope();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::{Param, Ty};
+
+    fn span(n: u32) -> Span {
+        Span::new(n, n + 1)
+    }
+
+    fn var(name: &str, n: u32) -> Expr {
+        Expr::Var {
+            name: name.to_string(),
+            span: span(n),
+        }
+    }
+
+    fn borrow_mut(name: &str, n: u32) -> Expr {
+        Expr::Borrow {
+            mutable: true,
+            inner: Box::new(var(name, n)),
+            span: span(n),
+        }
+    }
+
+    fn func(params: Vec<&str>, stmts: Vec<Stmt>) -> Function {
+        Function {
+            name: "test".to_string(),
+            params: params
+                .into_iter()
+                .map(|name| Param {
+                    name: name.to_string(),
+                    ty: Ty::Named("Circle".to_string()),
+                    span: span(0),
+                })
+                .collect(),
+            ret: Ty::Unit,
+            body: Block { stmts, span: span(0) },
+        }
+    }
+
+    fn check(f: &Function) -> DiagnosticBag {
+        let mut bag = DiagnosticBag::new();
+        check_function(f, &mut bag, &Tracer::disabled());
+        bag
+    }
+
+    #[test]
+    fn use_after_move_is_rejected() {
+        let f = func(
+            vec!["x"],
+            vec![
+                Stmt::Let {
+                    name: "y".to_string(),
+                    ty: Ty::Named("Circle".to_string()),
+                    init: var("x", 1),
+                    span: span(1),
+                },
+                Stmt::Expr(var("x", 2)),
+            ],
+        );
+        let bag = check(&f);
+        assert!(bag.has_errors(), "expected a use-after-move diagnostic");
+    }
+
+    #[test]
+    fn sequential_calls_each_taking_a_fresh_mutable_borrow_are_allowed() {
+        // Regression test: `f(&mut x); f(&mut x);` must not conflict with
+        // itself — each borrow is a call argument, not bound to anything
+        // that outlives its call.
+        let f = func(
+            vec!["x"],
+            vec![
+                Stmt::Expr(Expr::Call {
+                    callee: "f".to_string(),
+                    args: vec![borrow_mut("x", 1)],
+                    span: span(1),
+                }),
+                Stmt::Expr(Expr::Call {
+                    callee: "f".to_string(),
+                    args: vec![borrow_mut("x", 2)],
+                    span: span(2),
+                }),
+            ],
+        );
+        let bag = check(&f);
+        assert!(
+            !bag.has_errors(),
+            "sequential call-argument borrows should not conflict"
+        );
+    }
+
+    #[test]
+    fn two_let_bound_mutable_borrows_of_the_same_place_conflict() {
+        let f = func(
+            vec!["x"],
+            vec![
+                Stmt::Let {
+                    name: "r1".to_string(),
+                    ty: Ty::Ref {
+                        mutable: true,
+                        inner: Box::new(Ty::Named("Circle".to_string())),
+                    },
+                    init: borrow_mut("x", 1),
+                    span: span(1),
+                },
+                Stmt::Let {
+                    name: "r2".to_string(),
+                    ty: Ty::Ref {
+                        mutable: true,
+                        inner: Box::new(Ty::Named("Circle".to_string())),
+                    },
+                    init: borrow_mut("x", 2),
+                    span: span(2),
+                },
+            ],
+        );
+        let bag = check(&f);
+        assert!(
+            bag.has_errors(),
+            "two let-bound mutable borrows of `x` must conflict"
+        );
+    }
+
+    #[test]
+    fn for_loop_body_releases_temporary_borrows_between_statements() {
+        // Before the fix, `for` walked its body with raw `check_stmt` calls
+        // instead of `check_block`, so temporary borrows never released.
+        let f = func(
+            vec!["x"],
+            vec![Stmt::For {
+                index: "i".to_string(),
+                binder: "v".to_string(),
+                iter: Expr::Literal {
+                    value: crate::hir::LiteralValue::Int(0),
+                    span: span(0),
+                },
+                body: Block {
+                    stmts: vec![
+                        Stmt::Expr(Expr::Call {
+                            callee: "f".to_string(),
+                            args: vec![borrow_mut("x", 1)],
+                            span: span(1),
+                        }),
+                        Stmt::Expr(Expr::Call {
+                            callee: "f".to_string(),
+                            args: vec![borrow_mut("x", 2)],
+                            span: span(2),
+                        }),
+                    ],
+                    span: span(1),
+                },
+                span: span(0),
+            }],
+        );
+        let bag = check(&f);
+        assert!(
+            !bag.has_errors(),
+            "sequential call-argument borrows inside a `for` body should not conflict"
+        );
+    }
+
+    #[test]
+    fn reading_a_field_after_the_whole_struct_was_moved_is_rejected() {
+        // Regression test: `let y := c; log(c.radius);` must be caught —
+        // moving `c` invalidates every field underneath it too.
+        let f = func(
+            vec!["c"],
+            vec![
+                Stmt::Let {
+                    name: "y".to_string(),
+                    ty: Ty::Named("Circle".to_string()),
+                    init: var("c", 1),
+                    span: span(1),
+                },
+                Stmt::Expr(Expr::Call {
+                    callee: "log".to_string(),
+                    args: vec![Expr::Field {
+                        base: Box::new(var("c", 2)),
+                        field: "radius".to_string(),
+                        span: span(2),
+                    }],
+                    span: span(2),
+                }),
+            ],
+        );
+        let bag = check(&f);
+        assert!(
+            bag.has_errors(),
+            "reading a field of a moved-from struct must be rejected"
+        );
+    }
+
+    #[test]
+    fn reassigning_a_place_clears_stale_moved_state_for_its_fields() {
+        // Regression test: `let y := c.radius; c = other; log(c.radius);`
+        // must NOT be rejected — `c = other;` fully re-initializes `c`, so
+        // the earlier move of `c.radius` no longer applies.
+        let f = func(
+            vec!["c", "other"],
+            vec![
+                Stmt::Let {
+                    name: "y".to_string(),
+                    ty: Ty::Named("Circle".to_string()),
+                    init: Expr::Field {
+                        base: Box::new(var("c", 1)),
+                        field: "radius".to_string(),
+                        span: span(1),
+                    },
+                    span: span(1),
+                },
+                Stmt::Assign {
+                    target: var("c", 2),
+                    value: var("other", 2),
+                    span: span(2),
+                },
+                Stmt::Expr(Expr::Call {
+                    callee: "log".to_string(),
+                    args: vec![Expr::Field {
+                        base: Box::new(var("c", 3)),
+                        field: "radius".to_string(),
+                        span: span(3),
+                    }],
+                    span: span(3),
+                }),
+            ],
+        );
+        let bag = check(&f);
+        assert!(
+            !bag.has_errors(),
+            "reading `c.radius` after `c` was wholly reassigned must not be rejected as moved"
+        );
+    }
+
+    #[test]
+    fn while_loops_two_pass_approximation_does_not_duplicate_diagnostics() {
+        // Regression test: a same-iteration use-after-move inside a `while`
+        // body (nothing loop-specific) must be reported once, not once per
+        // pass of the two-pass back-edge approximation.
+        let f = func(
+            vec!["x"],
+            vec![Stmt::While {
+                cond: Expr::Literal {
+                    value: crate::hir::LiteralValue::Bool(true),
+                    span: span(0),
+                },
+                body: Block {
+                    stmts: vec![
+                        Stmt::Let {
+                            name: "y".to_string(),
+                            ty: Ty::Named("Circle".to_string()),
+                            init: var("x", 1),
+                            span: span(1),
+                        },
+                        Stmt::Expr(var("x", 2)),
+                    ],
+                    span: span(1),
+                },
+                span: span(0),
+            }],
+        );
+        let bag = check(&f);
+        let use_after_move_count = bag
+            .iter()
+            .filter(|d| d.message.contains("use of moved value"))
+            .count();
+        assert_eq!(
+            use_after_move_count, 1,
+            "a same-iteration use-after-move must be reported exactly once"
+        );
+    }
+}
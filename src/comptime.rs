@@ -0,0 +1,463 @@
+//! Compile-time evaluation engine for `comptime` expressions.
+//!
+//! A tree-walking interpreter over [`hir`] that evaluates arithmetic, struct
+//! literals, fixed-size array indexing, enum values, and calls to other
+//! comptime-eligible functions, then materializes the result as a
+//! [`ConstValue`] so later passes see a plain literal rather than the
+//! expression that produced it (see [`hir::Expr::Const`]).
+//!
+//! Side-effecting calls (`log`, `fetch`, anything not found among the
+//! functions passed in) are rejected with a diagnostic rather than executed,
+//! and a step/recursion budget guards against a `comptime` function that
+//! never terminates.
+
+use std::collections::HashMap;
+
+use crate::diagnostics::{Diagnostic, DiagnosticBag, Span};
+use crate::hir::{BinOp, Block, ConstValue, Expr, Function, LiteralValue, Pattern, Stmt};
+use crate::trace::{self, Tracer};
+
+/// Builtins that exist purely for their side effects and can never be
+/// comptime-eligible, regardless of how they're declared.
+const SIDE_EFFECTING_BUILTINS: &[&str] = &["log", "fetch"];
+
+pub struct Limits {
+    pub max_steps: u32,
+    pub max_call_depth: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_steps: 100_000,
+            max_call_depth: 256,
+        }
+    }
+}
+
+enum Flow {
+    Normal,
+    Return(ConstValue),
+}
+
+pub struct Evaluator<'a> {
+    functions: &'a HashMap<String, &'a Function>,
+    limits: Limits,
+    steps: u32,
+    call_depth: u32,
+    scopes: Vec<HashMap<String, ConstValue>>,
+    tracer: &'a Tracer,
+}
+
+/// Evaluates a call to a `comptime`-marked function and materializes its
+/// result, or reports why it could not be evaluated at compile time.
+pub fn evaluate(
+    callee: &str,
+    args: &[Expr],
+    span: Span,
+    functions: &HashMap<String, &Function>,
+    bag: &mut DiagnosticBag,
+    tracer: &Tracer,
+) -> Option<ConstValue> {
+    let _span_guard = tracer.span("comptime", callee.to_string(), &[trace::field("args", args.len())]);
+    let mut eval = Evaluator {
+        functions,
+        limits: Limits::default(),
+        steps: 0,
+        call_depth: 0,
+        scopes: Vec::new(),
+        tracer,
+    };
+    match eval.call(callee, args, span) {
+        Ok(value) => Some(value),
+        Err(diagnostic) => {
+            bag.push(diagnostic);
+            None
+        }
+    }
+}
+
+impl<'a> Evaluator<'a> {
+    fn step(&mut self, span: Span) -> Result<(), Diagnostic> {
+        self.steps += 1;
+        if self.steps > self.limits.max_steps {
+            return Err(Diagnostic::error(
+                span,
+                "comptime evaluation exceeded its step limit; the function may not terminate",
+            ));
+        }
+        Ok(())
+    }
+
+    fn lookup(&self, name: &str) -> Option<ConstValue> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).cloned())
+    }
+
+    fn bind(&mut self, name: String, value: ConstValue) {
+        self.scopes.last_mut().expect("at least one scope").insert(name, value);
+    }
+
+    fn call(&mut self, callee: &str, args: &[Expr], span: Span) -> Result<ConstValue, Diagnostic> {
+        if SIDE_EFFECTING_BUILTINS.contains(&callee) {
+            return Err(Diagnostic::error(
+                span,
+                format!("`{callee}` performs I/O and cannot be called from a `comptime` context"),
+            ));
+        }
+        let Some(func) = self.functions.get(callee) else {
+            return Err(Diagnostic::error(
+                span,
+                format!("`{callee}` is not comptime-eligible: no comptime-reachable definition was found"),
+            ));
+        };
+        self.call_depth += 1;
+        if self.call_depth > self.limits.max_call_depth {
+            self.call_depth -= 1;
+            return Err(Diagnostic::error(
+                span,
+                "comptime evaluation exceeded its recursion limit",
+            ));
+        }
+        let _span_guard = self.tracer.span(
+            "comptime",
+            callee.to_string(),
+            &[trace::field("depth", self.call_depth)],
+        );
+
+        let mut arg_values = Vec::with_capacity(args.len());
+        for arg in args {
+            arg_values.push(self.eval_expr(arg)?);
+        }
+        let mut frame = HashMap::new();
+        for (param, value) in func.params.iter().zip(arg_values) {
+            frame.insert(param.name.clone(), value);
+        }
+        self.scopes.push(frame);
+        let result = match self.eval_block(&func.body)? {
+            Flow::Return(value) => value,
+            Flow::Normal => ConstValue::Bool(false), // unit-returning functions have nothing to fold
+        };
+        self.scopes.pop();
+        self.call_depth -= 1;
+        Ok(result)
+    }
+
+    fn eval_block(&mut self, block: &Block) -> Result<Flow, Diagnostic> {
+        self.scopes.push(HashMap::new());
+        let result = self.eval_stmts(&block.stmts);
+        self.scopes.pop();
+        result
+    }
+
+    fn eval_stmts(&mut self, stmts: &[Stmt]) -> Result<Flow, Diagnostic> {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Let { name, init, .. } => {
+                    let value = self.eval_expr(init)?;
+                    self.bind(name.clone(), value);
+                }
+                Stmt::Assign { target, value, span } => {
+                    let Expr::Var { name, .. } = target else {
+                        return Err(Diagnostic::error(
+                            *span,
+                            "comptime evaluation only supports assigning to a plain variable",
+                        ));
+                    };
+                    let value = self.eval_expr(value)?;
+                    // Update the variable where it was declared, so an
+                    // assignment inside a loop body outlives that block.
+                    match self.scopes.iter_mut().rev().find(|scope| scope.contains_key(name)) {
+                        Some(scope) => {
+                            scope.insert(name.clone(), value);
+                        }
+                        None => self.bind(name.clone(), value),
+                    }
+                }
+                Stmt::Expr(expr) => {
+                    self.eval_expr(expr)?;
+                }
+                Stmt::While { cond, body, span } => {
+                    while self.eval_bool(cond)? {
+                        self.step(*span)?;
+                        if let Flow::Return(value) = self.eval_block(body)? {
+                            return Ok(Flow::Return(value));
+                        }
+                    }
+                }
+                Stmt::For { .. } => {
+                    return Err(Diagnostic::error(
+                        stmt_span(stmt),
+                        "comptime evaluation does not yet support `for` loops",
+                    ));
+                }
+                Stmt::When { scrutinee, arms, span } => {
+                    let value = self.eval_expr(scrutinee)?;
+                    let mut matched = false;
+                    for arm in arms {
+                        if let Some(bindings) = match_pattern(&value, &arm.pattern) {
+                            matched = true;
+                            self.scopes.push(bindings.into_iter().collect());
+                            let flow = self.eval_stmts(&arm.body.stmts)?;
+                            self.scopes.pop();
+                            if let Flow::Return(value) = flow {
+                                return Ok(Flow::Return(value));
+                            }
+                            break;
+                        }
+                    }
+                    if !matched {
+                        return Err(Diagnostic::error(
+                            *span,
+                            "comptime `when` did not match any arm for this value",
+                        ));
+                    }
+                }
+                Stmt::Return { value, span } => {
+                    let value = match value {
+                        Some(expr) => self.eval_expr(expr)?,
+                        None => ConstValue::Bool(false),
+                    };
+                    let _ = span;
+                    return Ok(Flow::Return(value));
+                }
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn eval_bool(&mut self, expr: &Expr) -> Result<bool, Diagnostic> {
+        match self.eval_expr(expr)? {
+            ConstValue::Bool(b) => Ok(b),
+            other => Err(Diagnostic::error(
+                expr.span(),
+                format!("expected a `bool` in comptime evaluation, found {other:?}"),
+            )),
+        }
+    }
+
+    fn eval_expr(&mut self, expr: &Expr) -> Result<ConstValue, Diagnostic> {
+        self.step(expr.span())?;
+        match expr {
+            Expr::Literal { value, .. } => Ok(match value {
+                LiteralValue::Int(n) => ConstValue::Int(*n),
+                LiteralValue::Float(f) => ConstValue::Float(*f),
+                LiteralValue::Str(s) => ConstValue::Str(s.clone()),
+                LiteralValue::Bool(b) => ConstValue::Bool(*b),
+            }),
+            Expr::Const { value, .. } => Ok(value.clone()),
+            Expr::Var { name, span } => self
+                .lookup(name)
+                .ok_or_else(|| Diagnostic::error(*span, format!("`{name}` is not defined in this comptime context"))),
+            Expr::Field { base, field, span } => {
+                let base = self.eval_expr(base)?;
+                match base {
+                    ConstValue::Struct { fields, .. } => fields
+                        .into_iter()
+                        .find(|(name, _)| name == field)
+                        .map(|(_, value)| value)
+                        .ok_or_else(|| Diagnostic::error(*span, format!("no field `{field}` in this comptime struct value"))),
+                    other => Err(Diagnostic::error(
+                        *span,
+                        format!("`.{field}` requires a struct value, found {other:?}"),
+                    )),
+                }
+            }
+            Expr::Index { base, index, span } => {
+                let base = self.eval_expr(base)?;
+                let index = self.eval_expr(index)?;
+                let (ConstValue::Array(elements), ConstValue::Int(i)) = (base, index) else {
+                    return Err(Diagnostic::error(*span, "array indexing requires an array and an integer index"));
+                };
+                let i = usize::try_from(i).map_err(|_| Diagnostic::error(*span, "array index is negative"))?;
+                elements
+                    .get(i)
+                    .cloned()
+                    .ok_or_else(|| Diagnostic::error(*span, format!("array index {i} is out of bounds")))
+            }
+            Expr::ArrayLiteral { elements, .. } => {
+                let mut values = Vec::with_capacity(elements.len());
+                for element in elements {
+                    values.push(self.eval_expr(element)?);
+                }
+                Ok(ConstValue::Array(values))
+            }
+            Expr::StructLiteral { ty_name, fields, .. } => {
+                let mut values = Vec::with_capacity(fields.len());
+                for (name, value) in fields {
+                    values.push((name.clone(), self.eval_expr(value)?));
+                }
+                Ok(ConstValue::Struct {
+                    ty_name: ty_name.clone(),
+                    fields: values,
+                })
+            }
+            Expr::Call { callee, args, span } => self.call(callee, args, *span),
+            Expr::Binary { op, lhs, rhs, span } => {
+                let lhs = self.eval_expr(lhs)?;
+                let rhs = self.eval_expr(rhs)?;
+                eval_binary(*op, lhs, rhs, *span)
+            }
+            Expr::Ternary {
+                cond,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                if self.eval_bool(cond)? {
+                    self.eval_expr(then_branch)
+                } else {
+                    self.eval_expr(else_branch)
+                }
+            }
+            Expr::EnumLiteral { enum_name, variant, .. } => Ok(ConstValue::EnumTag {
+                enum_name: enum_name.clone(),
+                variant: variant.clone(),
+            }),
+            Expr::Borrow { span, .. } => Err(Diagnostic::error(
+                *span,
+                "comptime evaluation does not support taking references",
+            )),
+            Expr::Try { span, .. } => Err(Diagnostic::error(
+                *span,
+                "comptime evaluation does not yet support `?` error propagation",
+            )),
+        }
+    }
+}
+
+fn stmt_span(stmt: &Stmt) -> Span {
+    match stmt {
+        Stmt::Let { span, .. }
+        | Stmt::Assign { span, .. }
+        | Stmt::While { span, .. }
+        | Stmt::For { span, .. }
+        | Stmt::When { span, .. }
+        | Stmt::Return { span, .. } => *span,
+        Stmt::Expr(expr) => expr.span(),
+    }
+}
+
+fn eval_binary(op: BinOp, lhs: ConstValue, rhs: ConstValue, span: Span) -> Result<ConstValue, Diagnostic> {
+    use ConstValue::*;
+    match (op, lhs, rhs) {
+        (BinOp::Add, Int(a), Int(b)) => Ok(Int(a + b)),
+        (BinOp::Add, Float(a), Float(b)) => Ok(Float(a + b)),
+        (BinOp::Add, Str(a), Str(b)) => Ok(Str(a + &b)),
+        (BinOp::Sub, Int(a), Int(b)) => Ok(Int(a - b)),
+        (BinOp::Sub, Float(a), Float(b)) => Ok(Float(a - b)),
+        (BinOp::Mul, Int(a), Int(b)) => Ok(Int(a * b)),
+        (BinOp::Mul, Float(a), Float(b)) => Ok(Float(a * b)),
+        (BinOp::Div, Int(a), Int(b)) => {
+            if b == 0 {
+                Err(Diagnostic::error(span, "comptime division by zero"))
+            } else {
+                Ok(Int(a / b))
+            }
+        }
+        (BinOp::Div, Float(a), Float(b)) => Ok(Float(a / b)),
+        (BinOp::Eq, a, b) => Ok(Bool(a == b)),
+        (BinOp::Lt, Int(a), Int(b)) => Ok(Bool(a < b)),
+        (BinOp::Lt, Float(a), Float(b)) => Ok(Bool(a < b)),
+        (op, a, b) => Err(Diagnostic::error(
+            span,
+            format!("`{op:?}` is not defined for {a:?} and {b:?} in a comptime context"),
+        )),
+    }
+}
+
+/// Mirrors [`crate::matchck`]'s constructor view of a pattern, but against a
+/// concrete value instead of a type, returning the bindings introduced if it
+/// matches.
+fn match_pattern(value: &ConstValue, pattern: &Pattern) -> Option<Vec<(String, ConstValue)>> {
+    match pattern {
+        Pattern::Wildcard => Some(Vec::new()),
+        Pattern::Binding(name) => Some(vec![(name.clone(), value.clone())]),
+        Pattern::Null => None, // `comptime` values are never optional-null today.
+        Pattern::Bool(b) => matches!(value, ConstValue::Bool(v) if v == b).then(Vec::new),
+        Pattern::EnumVariant { enum_name, variant } => {
+            matches!(value, ConstValue::EnumTag { enum_name: e, variant: v } if e == enum_name && v == variant)
+                .then(Vec::new)
+        }
+        Pattern::Struct { fields, .. } => {
+            let ConstValue::Struct { fields: value_fields, .. } = value else {
+                return None;
+            };
+            let mut bindings = Vec::new();
+            for (name, sub_pattern) in fields {
+                let field_value = value_fields.iter().find(|(n, _)| n == name).map(|(_, v)| v)?;
+                bindings.extend(match_pattern(field_value, sub_pattern)?);
+            }
+            Some(bindings)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::Param;
+
+    fn span(n: u32) -> Span {
+        Span::new(n, n + 1)
+    }
+
+    fn func(body: Vec<Stmt>) -> Function {
+        Function {
+            name: "test".to_string(),
+            params: Vec::<Param>::new(),
+            ret: crate::hir::Ty::Bool,
+            body: Block { stmts: body, span: span(0) },
+        }
+    }
+
+    fn run(func: &Function) -> Result<ConstValue, Diagnostic> {
+        let functions = HashMap::new();
+        let mut eval = Evaluator {
+            functions: &functions,
+            limits: Limits::default(),
+            steps: 0,
+            call_depth: 0,
+            scopes: vec![HashMap::new()],
+            tracer: &Tracer::disabled(),
+        };
+        match eval.eval_block(&func.body)? {
+            Flow::Return(value) => Ok(value),
+            Flow::Normal => Ok(ConstValue::Bool(false)),
+        }
+    }
+
+    #[test]
+    fn step_limit_exceeded_is_rejected() {
+        // `while true {}` never terminates, so the step budget must kick in
+        // rather than hanging the compiler.
+        let f = func(vec![Stmt::While {
+            cond: Expr::Literal {
+                value: LiteralValue::Bool(true),
+                span: span(0),
+            },
+            body: Block { stmts: Vec::new(), span: span(0) },
+            span: span(0),
+        }]);
+        let err = run(&f).expect_err("an unbounded loop must hit the step limit");
+        assert!(err.message.contains("step limit"));
+    }
+
+    #[test]
+    fn io_functions_are_rejected_from_comptime_context() {
+        let functions = HashMap::new();
+        let mut bag = DiagnosticBag::new();
+        let result = evaluate(
+            "log",
+            &[Expr::Literal {
+                value: LiteralValue::Str("hi".to_string()),
+                span: span(0),
+            }],
+            span(0),
+            &functions,
+            &mut bag,
+            &Tracer::disabled(),
+        );
+        assert!(result.is_none());
+        assert!(bag.has_errors());
+    }
+}
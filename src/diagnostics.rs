@@ -0,0 +1,143 @@
+//! Shared diagnostic plumbing used by every compiler pass.
+//!
+//! Passes never print directly; they push [`Diagnostic`]s into a
+//! [`DiagnosticBag`] and the driver decides how (and whether) to render them.
+
+use std::collections::HashSet;
+
+/// A half-open byte range into the source file a pass is currently analyzing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Span {
+    pub const fn new(start: u32, end: u32) -> Self {
+        Self { start, end }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    /// Recorded but never surfaced; used by the lint driver for rules a user
+    /// has downgraded to `allow`.
+    Allow,
+}
+
+/// One `span: message` annotation attached to a diagnostic. A diagnostic
+/// always has a primary label and may carry secondary labels pointing at
+/// related spans (e.g. the original borrow a conflicting borrow clashes with).
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub primary: Label,
+    pub secondary: Vec<Label>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            primary: Label {
+                span,
+                message: String::new(),
+            },
+            secondary: Vec::new(),
+        }
+    }
+
+    pub fn error(span: Span, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, span, message)
+    }
+
+    pub fn warning(span: Span, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, span, message)
+    }
+
+    /// Attach a secondary label, e.g. to point back at the span of a
+    /// conflicting prior borrow or an unused declaration's definition site.
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.secondary.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+}
+
+/// Accumulates diagnostics for a single compilation; every pass takes a
+/// `&mut DiagnosticBag` rather than returning its own `Vec<Diagnostic>` so
+/// later passes can still run (and report further errors) after earlier ones
+/// found problems.
+#[derive(Debug, Default)]
+pub struct DiagnosticBag {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticBag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn into_vec(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+
+    /// Removes diagnostics at or after `first_pass.end` whose `message`
+    /// matches one already pushed during `first_pass` — for passes that
+    /// re-walk the same syntax twice to approximate a loop back-edge, so
+    /// a problem that's already broken on the first walk (and therefore
+    /// cascades into further statements once state carries over into the
+    /// second walk) isn't reported once per statement it touches. Keyed on
+    /// the message alone, not `(span, message)`: the whole point is that the
+    /// second walk's re-reports of the *same* already-known problem land at
+    /// different spans (a later statement that now also observes the stale
+    /// state), not the same one.
+    pub fn dedupe_repeated_walk(&mut self, first_pass: std::ops::Range<usize>) {
+        let seen: HashSet<String> = self.diagnostics[first_pass.clone()]
+            .iter()
+            .map(|d| d.message.clone())
+            .collect();
+        let mut i = first_pass.end;
+        while i < self.diagnostics.len() {
+            if seen.contains(&self.diagnostics[i].message) {
+                self.diagnostics.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
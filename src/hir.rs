@@ -0,0 +1,259 @@
+//! High-level IR: the semantic representation passes run over once name
+//! resolution and type inference have already assigned every expression a
+//! [`Ty`]. Lower-level than the parser's AST, higher-level than anything
+//! codegen-specific.
+//!
+//! Bindings are identified by name rather than a numeric id — this crate does
+//! not yet have a symbol table, so `hir` scopes names the same way the
+//! surface language does (shadowing included) and leaves de-duplication to
+//! later work.
+
+use crate::diagnostics::Span;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ty {
+    I32,
+    F32,
+    Str,
+    Bool,
+    /// A user-defined struct, enum, or interface, named by the declaration.
+    Named(String),
+    /// `&T` (shared) or `&mut T` (exclusive).
+    Ref { mutable: bool, inner: Box<Ty> },
+    /// `T?`.
+    Optional(Box<Ty>),
+    /// `Result ! E`, where `Ok` carries `ok` and the error carries `err`.
+    Result { ok: Box<Ty>, err: Box<Ty> },
+    Unit,
+}
+
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: String,
+    pub ty: Ty,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    /// For methods, e.g. `fn (c &Circle) area()`, the receiver is folded
+    /// into `params[0]`.
+    pub params: Vec<Param>,
+    pub ret: Ty,
+    pub body: Block,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub stmts: Vec<Stmt>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum Stmt {
+    Let {
+        name: String,
+        ty: Ty,
+        init: Expr,
+        span: Span,
+    },
+    Assign {
+        target: Expr,
+        value: Expr,
+        span: Span,
+    },
+    Expr(Expr),
+    While {
+        cond: Expr,
+        body: Block,
+        span: Span,
+    },
+    For {
+        index: String,
+        binder: String,
+        iter: Expr,
+        body: Block,
+        span: Span,
+    },
+    When {
+        scrutinee: Expr,
+        arms: Vec<WhenArm>,
+        span: Span,
+    },
+    Return {
+        value: Option<Expr>,
+        span: Span,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct WhenArm {
+    pub pattern: Pattern,
+    pub body: Block,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Null,
+    Wildcard,
+    Bool(bool),
+    EnumVariant { enum_name: String, variant: String },
+    Binding(String),
+    /// `Circle { .color = Color::Red }` — one pattern per named field,
+    /// matched in declaration order by [`crate::matchck`].
+    Struct {
+        ty_name: String,
+        fields: Vec<(String, Pattern)>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    Lt,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiteralValue {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+}
+
+/// A fully-evaluated value, either a literal as written or the result of
+/// [`crate::comptime`] folding a `comptime` expression. Both cases end up in
+/// [`Expr::Const`] so later passes (type checking, codegen) only ever see a
+/// literal, never the expression that produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    Array(Vec<ConstValue>),
+    Struct {
+        ty_name: String,
+        fields: Vec<(String, ConstValue)>,
+    },
+    EnumTag {
+        enum_name: String,
+        variant: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Var {
+        name: String,
+        span: Span,
+    },
+    Literal {
+        value: LiteralValue,
+        span: Span,
+    },
+    /// The materialized result of a `comptime` expression (see
+    /// [`crate::comptime::evaluate`]).
+    Const {
+        value: ConstValue,
+        span: Span,
+    },
+    Binary {
+        op: BinOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+        span: Span,
+    },
+    Index {
+        base: Box<Expr>,
+        index: Box<Expr>,
+        span: Span,
+    },
+    ArrayLiteral {
+        elements: Vec<Expr>,
+        span: Span,
+    },
+    Field {
+        base: Box<Expr>,
+        field: String,
+        span: Span,
+    },
+    /// `&expr` or `&mut expr`.
+    Borrow {
+        mutable: bool,
+        inner: Box<Expr>,
+        span: Span,
+    },
+    Call {
+        callee: String,
+        args: Vec<Expr>,
+        span: Span,
+    },
+    StructLiteral {
+        ty_name: String,
+        fields: Vec<(String, Expr)>,
+        span: Span,
+    },
+    /// The lowered form of postfix `expr?`: unwrap a `Result ! E`'s success
+    /// value, returning the error variant from the enclosing function early.
+    Try {
+        inner: Box<Expr>,
+        span: Span,
+    },
+    /// `cond ? then_branch : else_branch`, including the `cond ?: else`
+    /// Elvis shorthand (lowered as `then_branch == cond`).
+    Ternary {
+        cond: Box<Expr>,
+        then_branch: Box<Expr>,
+        else_branch: Box<Expr>,
+        span: Span,
+    },
+    /// `Color::Red` — a reference to one of an enum's variants, resolved by
+    /// name rather than carrying a value of its own.
+    EnumLiteral {
+        enum_name: String,
+        variant: String,
+        span: Span,
+    },
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Var { span, .. }
+            | Expr::Literal { span, .. }
+            | Expr::Const { span, .. }
+            | Expr::Binary { span, .. }
+            | Expr::Index { span, .. }
+            | Expr::ArrayLiteral { span, .. }
+            | Expr::Field { span, .. }
+            | Expr::Borrow { span, .. }
+            | Expr::Call { span, .. }
+            | Expr::StructLiteral { span, .. }
+            | Expr::Try { span, .. }
+            | Expr::Ternary { span, .. }
+            | Expr::EnumLiteral { span, .. } => *span,
+        }
+    }
+
+    /// The dotted place path this expression refers to (`c`, `c.radius`),
+    /// or `None` for expressions that do not name a place (calls, literals).
+    /// Used by the borrow checker to key partial moves/borrows of fields.
+    pub fn place_path(&self) -> Option<Vec<&str>> {
+        match self {
+            Expr::Var { name, .. } => Some(vec![name.as_str()]),
+            Expr::Field { base, field, .. } => {
+                let mut path = base.place_path()?;
+                path.push(field.as_str());
+                Some(path)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+//! Token kinds the parser consumes. Only a small set, centred on the
+//! postfix operators the current passes care about, is modeled here; this
+//! crate has no tokenizer that produces them.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Identifier(String),
+    Dot,
+    Question,
+    QuestionColon,
+    LParen,
+    RParen,
+    Comma,
+    Eof,
+}
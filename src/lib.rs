@@ -0,0 +1,11 @@
+pub mod ast;
+pub mod borrowck;
+pub mod comptime;
+pub mod diagnostics;
+pub mod hir;
+pub mod lexer;
+pub mod lint;
+pub mod matchck;
+pub mod parser;
+pub mod trace;
+pub mod typeck;
@@ -0,0 +1,74 @@
+//! Machine-applicable fixes a lint rule can attach to a diagnostic.
+
+use crate::diagnostics::Span;
+
+/// A single byte-range replacement. The driver applies a rule's fixes in one
+/// pass, source-ordered, and skips any fix whose span overlaps one already
+/// applied rather than risk corrupting the file.
+#[derive(Debug, Clone)]
+pub struct TextEdit {
+    pub span: Span,
+    pub replacement: String,
+}
+
+/// Applies non-overlapping `edits` to `source` and returns the rewritten
+/// text plus whichever edits were skipped because their span overlapped one
+/// applied earlier (sorted by start offset, earliest wins).
+pub fn apply_fixes(source: &str, mut edits: Vec<TextEdit>) -> (String, Vec<TextEdit>) {
+    edits.sort_by_key(|e| e.span.start);
+    let mut out = String::with_capacity(source.len());
+    let mut cursor = 0u32;
+    let mut skipped = Vec::new();
+    for edit in edits {
+        if edit.span.start < cursor {
+            skipped.push(edit);
+            continue;
+        }
+        out.push_str(&source[cursor as usize..edit.span.start as usize]);
+        out.push_str(&edit.replacement);
+        cursor = edit.span.end;
+    }
+    out.push_str(&source[cursor as usize..]);
+    (out, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(start: u32, end: u32, replacement: &str) -> TextEdit {
+        TextEdit {
+            span: Span::new(start, end),
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn non_overlapping_edits_are_all_applied() {
+        let (out, skipped) = apply_fixes("let x = 1;", vec![edit(4, 5, "y"), edit(8, 9, "2")]);
+        assert_eq!(out, "let y = 2;");
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn a_later_edit_overlapping_an_earlier_one_is_skipped() {
+        let (out, skipped) = apply_fixes("abcdef", vec![edit(0, 3, "X"), edit(2, 4, "Y")]);
+        assert_eq!(out, "Xdef", "the earlier edit wins and the overlapping one is dropped");
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].span, Span::new(2, 4));
+    }
+
+    #[test]
+    fn edits_are_applied_in_source_order_regardless_of_input_order() {
+        let (out, skipped) = apply_fixes("abcdef", vec![edit(4, 5, "Y"), edit(0, 1, "X")]);
+        assert_eq!(out, "XbcdYf");
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn an_edit_that_touches_but_does_not_overlap_the_previous_one_is_applied() {
+        let (out, skipped) = apply_fixes("abcdef", vec![edit(0, 2, "X"), edit(2, 4, "Y")]);
+        assert_eq!(out, "XYef");
+        assert!(skipped.is_empty());
+    }
+}
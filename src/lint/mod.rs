@@ -0,0 +1,231 @@
+//! A pluggable lint framework: independent rules walk the semantic model and
+//! report diagnostics with a configurable severity and an optional
+//! machine-applicable fix.
+//!
+//! Rules are read-only, so [`Driver::run`] fans them out over a thread per
+//! rule for a given function and merges their findings, mapping each one to
+//! the severity the user configured (defaulting to the rule's own). In
+//! `--fix` mode, [`Driver::collect_fixes`] gathers every rule's edits
+//! (including those from rules a user silenced) for [`fix::apply_fixes`].
+
+pub mod fix;
+pub mod rules;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::hir::Function;
+use fix::TextEdit;
+
+pub struct Finding {
+    pub rule: &'static str,
+    pub diagnostic: Diagnostic,
+    pub fix: Option<TextEdit>,
+}
+
+/// A single, independent check. Implementations must not reach outside the
+/// [`LintContext`] they're given — the driver runs every rule for the same
+/// function concurrently.
+pub trait LintRule: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn default_severity(&self) -> Severity;
+    fn check_function(&self, func: &Function, ctx: &mut LintContext);
+}
+
+#[derive(Default)]
+pub struct LintContext {
+    findings: Vec<(Diagnostic, Option<TextEdit>)>,
+}
+
+impl LintContext {
+    pub fn report(&mut self, diagnostic: Diagnostic) {
+        self.findings.push((diagnostic, None));
+    }
+
+    pub fn report_with_fix(&mut self, diagnostic: Diagnostic, fix: TextEdit) {
+        self.findings.push((diagnostic, Some(fix)));
+    }
+}
+
+/// Maps a rule's name to the severity a user configured for it, overriding
+/// [`LintRule::default_severity`]. A rule downgraded to [`Severity::Allow`]
+/// still runs — so `--fix` can still offer its edits — but is filtered out
+/// of [`Driver::run`]'s findings.
+#[derive(Default)]
+pub struct LintConfig {
+    overrides: HashMap<&'static str, Severity>,
+}
+
+impl LintConfig {
+    pub fn set(&mut self, rule: &'static str, severity: Severity) {
+        self.overrides.insert(rule, severity);
+    }
+
+    fn severity_for(&self, rule: &dyn LintRule) -> Severity {
+        self.overrides
+            .get(rule.name())
+            .copied()
+            .unwrap_or_else(|| rule.default_severity())
+    }
+}
+
+pub struct Driver {
+    rules: Vec<Box<dyn LintRule>>,
+    config: LintConfig,
+}
+
+impl Driver {
+    pub fn new(rules: Vec<Box<dyn LintRule>>, config: LintConfig) -> Self {
+        Self { rules, config }
+    }
+
+    /// Runs every rule over `func` in parallel, returning the findings whose
+    /// configured severity is not [`Severity::Allow`].
+    pub fn run(&self, func: &Function) -> Vec<Finding> {
+        let results = Mutex::new(Vec::new());
+        std::thread::scope(|scope| {
+            for rule in &self.rules {
+                let results = &results;
+                scope.spawn(move || {
+                    let mut ctx = LintContext::default();
+                    rule.check_function(func, &mut ctx);
+                    let mut results = results.lock().unwrap();
+                    for (mut diagnostic, fix) in ctx.findings {
+                        diagnostic.severity = self.config.severity_for(rule.as_ref());
+                        results.push(Finding {
+                            rule: rule.name(),
+                            diagnostic,
+                            fix,
+                        });
+                    }
+                });
+            }
+        });
+        let mut findings = results.into_inner().unwrap();
+        findings.retain(|f| f.diagnostic.severity != Severity::Allow);
+        findings
+    }
+
+    /// All fixes offered by any rule for `func`, regardless of the rule's
+    /// configured severity, for use by `--fix`.
+    pub fn collect_fixes(&self, func: &Function) -> Vec<TextEdit> {
+        let mut fixes = Vec::new();
+        for rule in &self.rules {
+            let mut ctx = LintContext::default();
+            rule.check_function(func, &mut ctx);
+            fixes.extend(ctx.findings.into_iter().filter_map(|(_, fix)| fix));
+        }
+        fixes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Span;
+    use crate::hir::{Block, Param, Ty};
+
+    fn span() -> Span {
+        Span::new(0, 1)
+    }
+
+    fn func() -> Function {
+        Function {
+            name: "test".to_string(),
+            params: Vec::<Param>::new(),
+            ret: Ty::Unit,
+            body: Block {
+                stmts: Vec::new(),
+                span: span(),
+            },
+        }
+    }
+
+    /// Always reports one finding with no fix, at whatever severity it's
+    /// constructed with.
+    struct AlwaysFindsRule(Severity);
+
+    impl LintRule for AlwaysFindsRule {
+        fn name(&self) -> &'static str {
+            "always_finds"
+        }
+
+        fn default_severity(&self) -> Severity {
+            self.0
+        }
+
+        fn check_function(&self, _func: &Function, ctx: &mut LintContext) {
+            ctx.report(Diagnostic::new(self.0, span(), "always finds something"));
+        }
+    }
+
+    /// Always reports one finding with a fix, for exercising `collect_fixes`.
+    struct AlwaysFixesRule;
+
+    impl LintRule for AlwaysFixesRule {
+        fn name(&self) -> &'static str {
+            "always_fixes"
+        }
+
+        fn default_severity(&self) -> Severity {
+            Severity::Warning
+        }
+
+        fn check_function(&self, _func: &Function, ctx: &mut LintContext) {
+            ctx.report_with_fix(
+                Diagnostic::warning(span(), "fixable"),
+                TextEdit {
+                    span: span(),
+                    replacement: String::new(),
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn run_merges_findings_from_every_rule() {
+        let driver = Driver::new(
+            vec![
+                Box::new(AlwaysFindsRule(Severity::Warning)),
+                Box::new(AlwaysFixesRule),
+            ],
+            LintConfig::default(),
+        );
+        let findings = driver.run(&func());
+        assert_eq!(findings.len(), 2);
+    }
+
+    #[test]
+    fn run_applies_each_rules_default_severity() {
+        let driver = Driver::new(vec![Box::new(AlwaysFindsRule(Severity::Error))], LintConfig::default());
+        let findings = driver.run(&func());
+        assert_eq!(findings[0].diagnostic.severity, Severity::Error);
+    }
+
+    #[test]
+    fn a_configured_severity_override_replaces_the_rules_default() {
+        let mut config = LintConfig::default();
+        config.set("always_finds", Severity::Error);
+        let driver = Driver::new(vec![Box::new(AlwaysFindsRule(Severity::Warning))], config);
+        let findings = driver.run(&func());
+        assert_eq!(findings[0].diagnostic.severity, Severity::Error);
+    }
+
+    #[test]
+    fn run_filters_out_findings_downgraded_to_allow() {
+        let mut config = LintConfig::default();
+        config.set("always_finds", Severity::Allow);
+        let driver = Driver::new(vec![Box::new(AlwaysFindsRule(Severity::Warning))], config);
+        assert!(driver.run(&func()).is_empty());
+    }
+
+    #[test]
+    fn collect_fixes_returns_fixes_even_for_rules_downgraded_to_allow() {
+        let mut config = LintConfig::default();
+        config.set("always_fixes", Severity::Allow);
+        let driver = Driver::new(vec![Box::new(AlwaysFixesRule)], config);
+        assert!(driver.run(&func()).is_empty(), "the finding itself is still filtered");
+        assert_eq!(driver.collect_fixes(&func()).len(), 1, "but its fix is still offered");
+    }
+}
@@ -0,0 +1,360 @@
+//! Initial lint rules: unused `let` bindings, ternaries that could be
+//! written as the Elvis operator, and `when` arms a wildcard already
+//! shadows.
+
+use std::collections::HashSet;
+
+use super::fix::TextEdit;
+use super::{LintContext, LintRule};
+use crate::diagnostics::{Diagnostic, Severity, Span};
+use crate::hir::{Block, Expr, Function, Pattern, Stmt};
+
+fn for_each_expr_in_stmt<'a>(stmt: &'a Stmt, f: &mut dyn FnMut(&'a Expr)) {
+    match stmt {
+        Stmt::Let { init, .. } => for_each_expr(init, f),
+        Stmt::Assign { target, value, .. } => {
+            for_each_expr(target, f);
+            for_each_expr(value, f);
+        }
+        Stmt::Expr(expr) => for_each_expr(expr, f),
+        Stmt::While { cond, body, .. } => {
+            for_each_expr(cond, f);
+            for_each_expr_in_block(body, f);
+        }
+        Stmt::For { iter, body, .. } => {
+            for_each_expr(iter, f);
+            for_each_expr_in_block(body, f);
+        }
+        Stmt::When { scrutinee, arms, .. } => {
+            for_each_expr(scrutinee, f);
+            for arm in arms {
+                for_each_expr_in_block(&arm.body, f);
+            }
+        }
+        Stmt::Return { value, .. } => {
+            if let Some(value) = value {
+                for_each_expr(value, f);
+            }
+        }
+    }
+}
+
+fn for_each_expr_in_block<'a>(block: &'a Block, f: &mut dyn FnMut(&'a Expr)) {
+    for stmt in &block.stmts {
+        for_each_expr_in_stmt(stmt, f);
+    }
+}
+
+fn for_each_expr<'a>(expr: &'a Expr, f: &mut dyn FnMut(&'a Expr)) {
+    f(expr);
+    match expr {
+        Expr::Var { .. } | Expr::Literal { .. } | Expr::Const { .. } | Expr::EnumLiteral { .. } => {}
+        Expr::Binary { lhs, rhs, .. } => {
+            for_each_expr(lhs, f);
+            for_each_expr(rhs, f);
+        }
+        Expr::Index { base, index, .. } => {
+            for_each_expr(base, f);
+            for_each_expr(index, f);
+        }
+        Expr::ArrayLiteral { elements, .. } => {
+            for element in elements {
+                for_each_expr(element, f);
+            }
+        }
+        Expr::Field { base, .. } => for_each_expr(base, f),
+        Expr::Borrow { inner, .. } => for_each_expr(inner, f),
+        Expr::Call { args, .. } => {
+            for arg in args {
+                for_each_expr(arg, f);
+            }
+        }
+        Expr::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                for_each_expr(value, f);
+            }
+        }
+        Expr::Try { inner, .. } => for_each_expr(inner, f),
+        Expr::Ternary {
+            cond,
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            for_each_expr(cond, f);
+            for_each_expr(then_branch, f);
+            for_each_expr(else_branch, f);
+        }
+    }
+}
+
+/// Flags a `let` binding whose name never appears as a variable anywhere
+/// else in the function, with a fix that deletes the whole statement. An
+/// assignment to the name counts as a use, so a binding that is only ever
+/// written to is not flagged.
+pub struct UnusedLetRule;
+
+impl LintRule for UnusedLetRule {
+    fn name(&self) -> &'static str {
+        "unused_let"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check_function(&self, func: &Function, ctx: &mut LintContext) {
+        let mut used = HashSet::new();
+        for_each_expr_in_block(&func.body, &mut |expr| {
+            if let Expr::Var { name, .. } = expr {
+                used.insert(name.clone());
+            }
+        });
+        check_block(&func.body, &used, ctx);
+    }
+}
+
+fn check_block(block: &Block, used: &HashSet<String>, ctx: &mut LintContext) {
+    for stmt in &block.stmts {
+        if let Stmt::Let { name, span, .. } = stmt {
+            if !used.contains(name) {
+                ctx.report_with_fix(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        *span,
+                        format!("unused binding `{name}`"),
+                    ),
+                    TextEdit {
+                        span: *span,
+                        replacement: String::new(),
+                    },
+                );
+            }
+        }
+        match stmt {
+            Stmt::While { body, .. } | Stmt::For { body, .. } => check_block(body, used, ctx),
+            Stmt::When { arms, .. } => {
+                for arm in arms {
+                    check_block(&arm.body, used, ctx)
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Flags `cond ? cond : else_branch`, which is exactly what the Elvis
+/// operator (`cond ?: else_branch`) means, and suggests the shorthand.
+pub struct ElvisRule;
+
+impl LintRule for ElvisRule {
+    fn name(&self) -> &'static str {
+        "redundant_ternary"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check_function(&self, func: &Function, ctx: &mut LintContext) {
+        for_each_expr_in_block(&func.body, &mut |expr| {
+            let Expr::Ternary {
+                cond,
+                then_branch,
+                else_branch,
+                span,
+            } = expr
+            else {
+                return;
+            };
+            if cond.place_path().is_some() && cond.place_path() == then_branch.place_path() {
+                ctx.report_with_fix(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        *span,
+                        "this ternary repeats its condition as the `then` branch; use the Elvis operator `?:`",
+                    ),
+                    TextEdit {
+                        span: Span::new(cond.span().end, else_branch.span().start),
+                        replacement: " ?: ".to_string(),
+                    },
+                );
+            }
+        });
+    }
+}
+
+/// Flags a `when` arm that can never run because an earlier `_`/binding arm
+/// already matches everything. [`crate::matchck`] performs the full
+/// constructor-based reachability analysis; this is the cheap syntactic
+/// subset of it offered as a lint so it can be downgraded independently.
+pub struct WhenWildcardShadowRule;
+
+impl LintRule for WhenWildcardShadowRule {
+    fn name(&self) -> &'static str {
+        "unreachable_when_arm"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check_function(&self, func: &Function, ctx: &mut LintContext) {
+        check_whens(&func.body, ctx);
+    }
+}
+
+fn check_whens(block: &Block, ctx: &mut LintContext) {
+    for stmt in &block.stmts {
+        match stmt {
+            Stmt::When { arms, .. } => {
+                let mut seen_wildcard = false;
+                for arm in arms {
+                    if seen_wildcard {
+                        ctx.report(Diagnostic::new(
+                            Severity::Warning,
+                            arm.span,
+                            "unreachable arm: an earlier `_` or binding arm already matches everything",
+                        ));
+                    }
+                    if matches!(arm.pattern, Pattern::Wildcard | Pattern::Binding(_)) {
+                        seen_wildcard = true;
+                    }
+                    check_whens(&arm.body, ctx);
+                }
+            }
+            Stmt::While { body, .. } | Stmt::For { body, .. } => check_whens(body, ctx),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Span;
+    use crate::hir::{Param, Ty, WhenArm};
+
+    fn span(n: u32) -> Span {
+        Span::new(n, n + 1)
+    }
+
+    fn var(name: &str, n: u32) -> Expr {
+        Expr::Var {
+            name: name.to_string(),
+            span: span(n),
+        }
+    }
+
+    fn func(stmts: Vec<Stmt>) -> Function {
+        Function {
+            name: "test".to_string(),
+            params: Vec::<Param>::new(),
+            ret: Ty::Unit,
+            body: Block { stmts, span: span(0) },
+        }
+    }
+
+    #[test]
+    fn unused_let_binding_is_flagged() {
+        let f = func(vec![Stmt::Let {
+            name: "y".to_string(),
+            ty: Ty::I32,
+            init: Expr::Literal {
+                value: crate::hir::LiteralValue::Int(1),
+                span: span(0),
+            },
+            span: span(1),
+        }]);
+        let mut ctx = LintContext::default();
+        UnusedLetRule.check_function(&f, &mut ctx);
+        assert_eq!(ctx.findings.len(), 1);
+    }
+
+    #[test]
+    fn let_binding_used_later_is_not_flagged() {
+        let f = func(vec![
+            Stmt::Let {
+                name: "y".to_string(),
+                ty: Ty::I32,
+                init: Expr::Literal {
+                    value: crate::hir::LiteralValue::Int(1),
+                    span: span(0),
+                },
+                span: span(1),
+            },
+            Stmt::Expr(var("y", 2)),
+        ]);
+        let mut ctx = LintContext::default();
+        UnusedLetRule.check_function(&f, &mut ctx);
+        assert!(ctx.findings.is_empty());
+    }
+
+    #[test]
+    fn ternary_repeating_its_condition_as_the_then_branch_is_flagged_with_a_fix() {
+        let f = func(vec![Stmt::Expr(Expr::Ternary {
+            cond: Box::new(var("c", 0)),
+            then_branch: Box::new(var("c", 1)),
+            else_branch: Box::new(var("other", 2)),
+            span: span(3),
+        })]);
+        let mut ctx = LintContext::default();
+        ElvisRule.check_function(&f, &mut ctx);
+        assert_eq!(ctx.findings.len(), 1);
+        assert!(ctx.findings[0].1.is_some(), "the Elvis rewrite must be offered as a fix");
+    }
+
+    #[test]
+    fn ternary_with_a_different_then_branch_is_not_flagged() {
+        let f = func(vec![Stmt::Expr(Expr::Ternary {
+            cond: Box::new(var("c", 0)),
+            then_branch: Box::new(var("other", 1)),
+            else_branch: Box::new(var("fallback", 2)),
+            span: span(3),
+        })]);
+        let mut ctx = LintContext::default();
+        ElvisRule.check_function(&f, &mut ctx);
+        assert!(ctx.findings.is_empty());
+    }
+
+    fn when(arms: Vec<WhenArm>) -> Stmt {
+        Stmt::When {
+            scrutinee: var("x", 0),
+            arms,
+            span: span(1),
+        }
+    }
+
+    fn when_arm(pattern: Pattern, n: u32) -> WhenArm {
+        WhenArm {
+            pattern,
+            body: Block {
+                stmts: Vec::new(),
+                span: span(n),
+            },
+            span: span(n),
+        }
+    }
+
+    #[test]
+    fn arm_after_a_wildcard_arm_is_flagged_unreachable() {
+        let f = func(vec![when(vec![
+            when_arm(Pattern::Wildcard, 0),
+            when_arm(Pattern::Bool(true), 1),
+        ])]);
+        let mut ctx = LintContext::default();
+        WhenWildcardShadowRule.check_function(&f, &mut ctx);
+        assert_eq!(ctx.findings.len(), 1);
+    }
+
+    #[test]
+    fn arms_with_no_preceding_wildcard_are_not_flagged() {
+        let f = func(vec![when(vec![
+            when_arm(Pattern::Bool(true), 0),
+            when_arm(Pattern::Bool(false), 1),
+        ])]);
+        let mut ctx = LintContext::default();
+        WhenWildcardShadowRule.check_function(&f, &mut ctx);
+        assert!(ctx.findings.is_empty());
+    }
+}
@@ -0,0 +1,47 @@
+//! Minimal compiler driver: wires up the `--trace` flag (see
+//! [`ferret_compiler::trace::Config`]) to an actual command line so tracing
+//! is something a user can turn on, not just something a pass knows how to
+//! accept.
+//!
+//! There's no lexer/parser pipeline wired end-to-end yet (see the individual
+//! pass modules), so this drives a single representative pass —
+//! [`ferret_compiler::parser::parse_postfix`] — to prove the flag reaches a
+//! real `Tracer`. As the passes are strung together into a full pipeline,
+//! this is where that wiring belongs.
+
+use std::iter::Peekable;
+
+use ferret_compiler::ast::Expr;
+use ferret_compiler::diagnostics::Span;
+use ferret_compiler::lexer::Token;
+use ferret_compiler::parser;
+use ferret_compiler::trace::{Config, Tracer};
+
+/// Parses `--trace`, `--trace=<level>`, or `--trace=<level>:<phases>` out of
+/// the process arguments; everything else is ignored (there's no other flag
+/// yet).
+fn trace_config_from_args() -> Config {
+    for arg in std::env::args().skip(1) {
+        if arg == "--trace" {
+            return Config::from_flag(None);
+        }
+        if let Some(value) = arg.strip_prefix("--trace=") {
+            return Config::from_flag(Some(value));
+        }
+    }
+    Config::default()
+}
+
+fn main() {
+    let tracer = Tracer::new(trace_config_from_args());
+    Tracer::install_panic_hook();
+
+    // Stand-in input until a real lexer/parser pipeline exists: `ok?`.
+    let primary = Expr::Var {
+        name: "ok".to_string(),
+        span: Span::new(0, 2),
+    };
+    let mut tokens: Peekable<std::vec::IntoIter<(Token, Span)>> =
+        vec![(Token::Question, Span::new(2, 3))].into_iter().peekable();
+    parser::parse_postfix(primary, &mut tokens, &tracer);
+}
@@ -0,0 +1,475 @@
+//! Exhaustiveness and reachability checking for `when` statements.
+//!
+//! Models the scrutinee's type as a finite set of constructors (enum
+//! variants, the `null`/present split of an optional, or booleans) and walks
+//! the arms top-to-bottom, shrinking the set of constructors not yet covered.
+//! An arm that covers nothing new is unreachable; if constructors remain
+//! uncovered after the last arm and none of them was a `_` wildcard, the
+//! `when` is non-exhaustive.
+//!
+//! Struct patterns (`Circle { .color = Color::Red }`) are handled by
+//! delegating to the single field they constrain: if every struct-pattern
+//! arm in a `when` narrows the same field, exhaustiveness is checked against
+//! that field's type instead of the struct's.
+//!
+//! A pattern that constrains more than one field at once (`Circle { .color =
+//! Color::Red, .radius = 0.0 }`) can't be delegated like that, so it's
+//! instead checked per field: each field is tracked as its own independent
+//! constructor set, shrunk by whichever arms mention that field, and the
+//! `when` is only considered exhaustive once every field's set is empty on
+//! its own. This is an AND-of-per-field approximation, not true
+//! product-space exhaustiveness — a set of arms that is exhaustive
+//! field-by-field can still miss a real combination (e.g. only
+//! `{Red, 0.0}` and `{Blue, 1.0}` are covered, not `{Red, 1.0}`) — but it
+//! catches the common case of a genuinely missing field value, which the
+//! old fully-opaque treatment never did. A nested struct pattern inside one
+//! of those fields falls back to the old opaque-but-reachable treatment,
+//! with a warning, since its own fields aren't tracked.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::diagnostics::{Diagnostic, DiagnosticBag, Severity, Span};
+use crate::hir::{Pattern, Ty, WhenArm};
+use crate::trace::{self, Tracer};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Ctor {
+    Variant(String),
+    Null,
+    /// The optional's "has a value" case. No surface syntax names it
+    /// directly — only `_` or a binding pattern covers it.
+    Present,
+    True,
+    False,
+}
+
+impl Ctor {
+    fn display(&self) -> String {
+        match self {
+            Ctor::Variant(v) => v.clone(),
+            Ctor::Null => "null".to_string(),
+            Ctor::Present => "a present value".to_string(),
+            Ctor::True => "true".to_string(),
+            Ctor::False => "false".to_string(),
+        }
+    }
+}
+
+enum Tag {
+    /// `_` or a bare binding: covers whatever constructors remain.
+    Wildcard,
+    Ctor(Ctor),
+    /// A struct pattern that couldn't be delegated to a single field.
+    Opaque,
+}
+
+#[derive(Default)]
+pub struct TypeRegistry {
+    pub enums: HashMap<String, Vec<String>>,
+    pub structs: HashMap<String, Vec<(String, Ty)>>,
+}
+
+/// The finite constructor set for `ty`, or `None` if `ty` has no such set
+/// (e.g. `i32`, `str`) — such types can only be made exhaustive with `_`.
+fn constructors_of(ty: &Ty, registry: &TypeRegistry) -> Option<HashSet<Ctor>> {
+    match ty {
+        Ty::Optional(_) => Some(HashSet::from([Ctor::Null, Ctor::Present])),
+        Ty::Bool => Some(HashSet::from([Ctor::True, Ctor::False])),
+        Ty::Named(name) => registry
+            .enums
+            .get(name)
+            .map(|variants| variants.iter().cloned().map(Ctor::Variant).collect()),
+        _ => None,
+    }
+}
+
+fn tag_of(pattern: &Pattern) -> Tag {
+    match pattern {
+        Pattern::Wildcard | Pattern::Binding(_) => Tag::Wildcard,
+        Pattern::Null => Tag::Ctor(Ctor::Null),
+        Pattern::Bool(b) => Tag::Ctor(if *b { Ctor::True } else { Ctor::False }),
+        Pattern::EnumVariant { variant, .. } => Tag::Ctor(Ctor::Variant(variant.clone())),
+        Pattern::Struct { fields, .. } => {
+            if fields.len() == 1 {
+                tag_of(&fields[0].1)
+            } else {
+                Tag::Opaque
+            }
+        }
+    }
+}
+
+/// When every struct-pattern arm narrows the same single field, returns that
+/// field's declared type so exhaustiveness can be checked against it instead
+/// of the (opaque) struct type.
+fn delegated_field_ty(arms: &[WhenArm], registry: &TypeRegistry) -> Option<Ty> {
+    let mut field_name: Option<&str> = None;
+    let mut struct_name: Option<&str> = None;
+    for arm in arms {
+        if let Pattern::Struct { ty_name, fields } = &arm.pattern {
+            let [(name, _)] = fields.as_slice() else {
+                return None;
+            };
+            match field_name {
+                Some(existing) if existing != name => return None,
+                _ => field_name = Some(name),
+            }
+            struct_name = Some(ty_name);
+        }
+    }
+    let (field_name, struct_name) = (field_name?, struct_name?);
+    registry
+        .structs
+        .get(struct_name)?
+        .iter()
+        .find(|(f, _)| f == field_name)
+        .map(|(_, ty)| ty.clone())
+}
+
+/// Checks one `when` statement's arms for unreachable arms and, if the
+/// scrutinee's type is finite, for missing constructors. `scrutinee_ty` is
+/// the type of the matched expression as inferred by the type checker.
+pub fn check_when(
+    scrutinee_ty: &Ty,
+    arms: &[WhenArm],
+    span: Span,
+    registry: &TypeRegistry,
+    bag: &mut DiagnosticBag,
+    tracer: &Tracer,
+) {
+    let _span_guard = tracer.span(
+        "matchck",
+        "check_when",
+        &[
+            trace::field("scrutinee_ty", format!("{scrutinee_ty:?}")),
+            trace::field("arms", arms.len()),
+        ],
+    );
+    let effective_ty = delegated_field_ty(arms, registry).unwrap_or_else(|| scrutinee_ty.clone());
+    let mut remaining = constructors_of(&effective_ty, registry);
+    let mut saw_wildcard = false;
+
+    // Independent per-field constructor sets for struct patterns that
+    // constrain more than one field (see the module doc comment). Lazily
+    // populated from the struct's registered field types the first time each
+    // field is mentioned.
+    let mut multi_field: HashMap<String, Option<HashSet<Ctor>>> = HashMap::new();
+
+    for arm in arms {
+        if saw_wildcard {
+            bag.push(Diagnostic::new(
+                Severity::Warning,
+                arm.span,
+                "unreachable `when` arm: all cases were already covered",
+            ));
+            continue;
+        }
+        if let Pattern::Struct { ty_name, fields } = &arm.pattern {
+            if fields.len() > 1 {
+                check_multi_field_arm(ty_name, fields, arm.span, registry, &mut multi_field, bag);
+                continue;
+            }
+        }
+        match tag_of(&arm.pattern) {
+            Tag::Wildcard => {
+                saw_wildcard = true;
+                if let Some(r) = &remaining {
+                    if r.is_empty() {
+                        bag.push(Diagnostic::new(
+                            Severity::Warning,
+                            arm.span,
+                            "unreachable `when` arm: all cases were already covered",
+                        ));
+                    }
+                }
+            }
+            Tag::Ctor(ctor) => {
+                if let Some(r) = remaining.as_mut() {
+                    if !r.remove(&ctor) {
+                        bag.push(Diagnostic::new(
+                            Severity::Warning,
+                            arm.span,
+                            format!("unreachable `when` arm: `{}` is already covered", ctor.display()),
+                        ));
+                    }
+                }
+            }
+            Tag::Opaque => {
+                bag.push(Diagnostic::new(
+                    Severity::Warning,
+                    arm.span,
+                    "this struct pattern constrains more than one field, so it can't be checked for exhaustiveness; add a `_` arm if every case isn't already covered",
+                ));
+            }
+        }
+    }
+
+    if !saw_wildcard {
+        if let Some(missing) = remaining {
+            if !missing.is_empty() {
+                let mut names: Vec<_> = missing.iter().map(Ctor::display).collect();
+                names.sort();
+                bag.push(Diagnostic::error(
+                    span,
+                    format!(
+                        "non-exhaustive `when`: missing case{} for {}",
+                        if names.len() == 1 { "" } else { "s" },
+                        names.join(", ")
+                    ),
+                ));
+            }
+        }
+
+        let mut incomplete: Vec<(String, Vec<String>)> = multi_field
+            .into_iter()
+            .filter_map(|(field, rem)| rem.map(|r| (field, r)))
+            .filter(|(_, remaining)| !remaining.is_empty())
+            .map(|(field, remaining)| {
+                let mut names: Vec<_> = remaining.iter().map(Ctor::display).collect();
+                names.sort();
+                (field, names)
+            })
+            .collect();
+        incomplete.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (field, names) in incomplete {
+            bag.push(Diagnostic::error(
+                span,
+                format!(
+                    "non-exhaustive `when`: field `{field}` is missing case{} for {}",
+                    if names.len() == 1 { "" } else { "s" },
+                    names.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+/// Checks one struct-pattern arm that constrains more than one field, per
+/// [`check_when`]'s AND-of-per-field approximation: shrinks `multi_field`'s
+/// entry for each field this arm mentions, and flags the arm unreachable if
+/// it didn't shrink any of them.
+fn check_multi_field_arm(
+    ty_name: &str,
+    fields: &[(String, Pattern)],
+    span: Span,
+    registry: &TypeRegistry,
+    multi_field: &mut HashMap<String, Option<HashSet<Ctor>>>,
+    bag: &mut DiagnosticBag,
+) {
+    let struct_fields = registry.structs.get(ty_name).map(Vec::as_slice).unwrap_or(&[]);
+    let mut covers_something_new = false;
+    for (field_name, field_pattern) in fields {
+        let remaining = multi_field.entry(field_name.clone()).or_insert_with(|| {
+            struct_fields
+                .iter()
+                .find(|(name, _)| name == field_name)
+                .and_then(|(_, ty)| constructors_of(ty, registry))
+        });
+        match tag_of(field_pattern) {
+            Tag::Wildcard => {
+                if let Some(r) = remaining {
+                    if !r.is_empty() {
+                        covers_something_new = true;
+                    }
+                    r.clear();
+                }
+            }
+            Tag::Ctor(ctor) => match remaining {
+                Some(r) => covers_something_new |= r.remove(&ctor),
+                // The field's type has no finite constructor set (e.g.
+                // `str`), so there's no way to tell whether this narrows
+                // anything further — assume it might rather than risk
+                // flagging a reachable arm as dead.
+                None => covers_something_new = true,
+            },
+            Tag::Opaque => {
+                covers_something_new = true;
+                bag.push(Diagnostic::new(
+                    Severity::Warning,
+                    span,
+                    format!("the nested struct pattern for field `{field_name}` can't be checked for exhaustiveness; add a `_` arm if every case isn't already covered"),
+                ));
+            }
+        }
+    }
+    if !covers_something_new {
+        bag.push(Diagnostic::new(
+            Severity::Warning,
+            span,
+            "unreachable `when` arm: every field this pattern constrains is already covered",
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(n: u32) -> Span {
+        Span::new(n, n + 1)
+    }
+
+    fn arm(pattern: Pattern) -> WhenArm {
+        WhenArm {
+            pattern,
+            body: crate::hir::Block {
+                stmts: Vec::new(),
+                span: span(0),
+            },
+            span: span(0),
+        }
+    }
+
+    fn color_registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::default();
+        registry.enums.insert(
+            "Color".to_string(),
+            vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+        );
+        registry
+    }
+
+    #[test]
+    fn missing_variant_is_non_exhaustive() {
+        let registry = color_registry();
+        let arms = vec![
+            arm(Pattern::EnumVariant {
+                enum_name: "Color".to_string(),
+                variant: "Red".to_string(),
+            }),
+            arm(Pattern::EnumVariant {
+                enum_name: "Color".to_string(),
+                variant: "Green".to_string(),
+            }),
+        ];
+        let mut bag = DiagnosticBag::new();
+        check_when(
+            &Ty::Named("Color".to_string()),
+            &arms,
+            span(0),
+            &registry,
+            &mut bag,
+            &Tracer::disabled(),
+        );
+        assert!(bag.has_errors(), "missing `Blue` arm must be reported");
+    }
+
+    #[test]
+    fn arm_after_wildcard_is_unreachable() {
+        let registry = color_registry();
+        let arms = vec![
+            arm(Pattern::Wildcard),
+            arm(Pattern::EnumVariant {
+                enum_name: "Color".to_string(),
+                variant: "Red".to_string(),
+            }),
+        ];
+        let mut bag = DiagnosticBag::new();
+        check_when(
+            &Ty::Named("Color".to_string()),
+            &arms,
+            span(0),
+            &registry,
+            &mut bag,
+            &Tracer::disabled(),
+        );
+        let warnings: Vec<_> = bag.iter().filter(|d| d.severity == Severity::Warning).collect();
+        assert!(
+            warnings.iter().any(|d| d.message.contains("unreachable")),
+            "arm after a wildcard must be flagged unreachable"
+        );
+    }
+
+    fn shape_registry() -> TypeRegistry {
+        let mut registry = color_registry();
+        registry.structs.insert(
+            "Shape".to_string(),
+            vec![("color".to_string(), Ty::Named("Color".to_string())), ("filled".to_string(), Ty::Bool)],
+        );
+        registry
+    }
+
+    fn shape_arm(color: &str, filled: bool) -> WhenArm {
+        arm(Pattern::Struct {
+            ty_name: "Shape".to_string(),
+            fields: vec![
+                (
+                    "color".to_string(),
+                    Pattern::EnumVariant {
+                        enum_name: "Color".to_string(),
+                        variant: color.to_string(),
+                    },
+                ),
+                ("filled".to_string(), Pattern::Bool(filled)),
+            ],
+        })
+    }
+
+    #[test]
+    fn multi_field_struct_pattern_missing_a_case_in_one_field_is_non_exhaustive() {
+        let registry = shape_registry();
+        let arms = vec![
+            shape_arm("Red", true),
+            shape_arm("Green", true),
+            shape_arm("Blue", true),
+        ];
+        let mut bag = DiagnosticBag::new();
+        check_when(
+            &Ty::Named("Shape".to_string()),
+            &arms,
+            span(0),
+            &registry,
+            &mut bag,
+            &Tracer::disabled(),
+        );
+        assert!(
+            bag.iter().any(|d| d.message.contains("field `filled`")),
+            "`filled` never matches `false` in any arm, so it must be reported missing"
+        );
+    }
+
+    #[test]
+    fn multi_field_struct_pattern_covering_every_value_of_every_field_is_exhaustive() {
+        let registry = shape_registry();
+        let arms = vec![
+            shape_arm("Red", true),
+            shape_arm("Red", false),
+            shape_arm("Green", true),
+            shape_arm("Green", false),
+            shape_arm("Blue", true),
+            shape_arm("Blue", false),
+        ];
+        let mut bag = DiagnosticBag::new();
+        check_when(
+            &Ty::Named("Shape".to_string()),
+            &arms,
+            span(0),
+            &registry,
+            &mut bag,
+            &Tracer::disabled(),
+        );
+        assert!(
+            !bag.has_errors(),
+            "every field's constructors are covered by some arm, so this must not be non-exhaustive"
+        );
+    }
+
+    #[test]
+    fn repeating_the_same_multi_field_struct_pattern_is_unreachable() {
+        let registry = shape_registry();
+        let arms = vec![shape_arm("Red", true), shape_arm("Red", true)];
+        let mut bag = DiagnosticBag::new();
+        check_when(
+            &Ty::Named("Shape".to_string()),
+            &arms,
+            span(0),
+            &registry,
+            &mut bag,
+            &Tracer::disabled(),
+        );
+        assert!(
+            bag.iter().any(|d| d.message.contains("unreachable")),
+            "a struct arm that shrinks neither field's remaining set must be flagged unreachable"
+        );
+    }
+}
@@ -0,0 +1,34 @@
+//! Postfix-operator parsing, layered on top of whatever parses primary
+//! expressions (identifiers, literals, struct literals, calls).
+
+use std::iter::Peekable;
+
+use crate::ast::Expr;
+use crate::diagnostics::Span;
+use crate::lexer::Token;
+use crate::trace::{self, Tracer};
+
+/// Having already parsed `primary`, consume zero or more trailing postfix
+/// operators. Currently only `?` (error propagation) binds at this level;
+/// `.field` and call-argument lists are assumed to have been folded into
+/// `primary` by the caller already.
+pub fn parse_postfix(
+    mut primary: Expr,
+    tokens: &mut Peekable<impl Iterator<Item = (Token, Span)>>,
+    tracer: &Tracer,
+) -> Expr {
+    let _span_guard = tracer.span(
+        "parser",
+        "parse_postfix",
+        &[trace::field("primary_span", format!("{:?}", primary.span()))],
+    );
+    while let Some((Token::Question, question_span)) = tokens.peek() {
+        let span = Span::new(primary.span().start, question_span.end);
+        tokens.next();
+        primary = Expr::Try {
+            inner: Box::new(primary),
+            span,
+        };
+    }
+    primary
+}
@@ -0,0 +1,233 @@
+//! `--trace` instrumentation: named, nested spans around each major pass so
+//! a maintainer can see exactly which phase and which node was being
+//! processed when something went wrong or got slow.
+//!
+//! Gated behind a CLI flag (`--trace`, `--trace=debug`,
+//! `--trace=debug:typeck,comptime`) parsed by [`Config::from_flag`]; disabled
+//! by default, in which case every [`Tracer`] method is close to free.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn parse(s: &str) -> Option<Level> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Some(Level::Error),
+            "warn" => Some(Level::Warn),
+            "info" => Some(Level::Info),
+            "debug" => Some(Level::Debug),
+            "trace" => Some(Level::Trace),
+            _ => None,
+        }
+    }
+}
+
+/// A `key=value` annotation recorded on a span: a node's span, a symbol
+/// name, an inferred type, or whatever else is useful to see at that point.
+pub struct Field {
+    pub key: &'static str,
+    pub value: String,
+}
+
+pub fn field(key: &'static str, value: impl std::fmt::Display) -> Field {
+    Field {
+        key,
+        value: value.to_string(),
+    }
+}
+
+#[derive(Clone)]
+pub struct Config {
+    pub enabled: bool,
+    pub level: Level,
+    /// Restricts emission to these phase names (`"typeck"`, `"comptime"`,
+    /// ...); `None` means every phase is traced.
+    pub phases: Option<HashSet<String>>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            level: Level::Info,
+            phases: None,
+        }
+    }
+}
+
+impl Config {
+    /// Parses the argument to `--trace`: bare (`--trace`) enables info-level
+    /// tracing of every phase; `--trace=debug` sets the level; and
+    /// `--trace=debug:typeck,comptime` additionally restricts emission to
+    /// the named phases.
+    pub fn from_flag(arg: Option<&str>) -> Self {
+        let Some(arg) = arg else {
+            return Self {
+                enabled: true,
+                ..Self::default()
+            };
+        };
+        let (level_part, phases_part) = match arg.split_once(':') {
+            Some((level, phases)) => (level, Some(phases)),
+            None => (arg, None),
+        };
+        let level = Level::parse(level_part).unwrap_or(Level::Info);
+        let phases = phases_part.map(|p| p.split(',').map(|s| s.trim().to_string()).collect());
+        Self {
+            enabled: true,
+            level,
+            phases,
+        }
+    }
+
+    fn allows(&self, level: Level, phase: &str) -> bool {
+        self.enabled
+            && level <= self.level
+            && self.phases.as_ref().is_none_or(|phases| phases.contains(phase))
+    }
+}
+
+thread_local! {
+    static ACTIVE_SPANS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+pub struct Tracer {
+    config: Config,
+}
+
+impl Tracer {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    pub fn disabled() -> Self {
+        Self::new(Config::default())
+    }
+
+    /// Enters a named span for `phase` (e.g. `"typeck"`), printing an entry
+    /// line indented to the current nesting depth if the filter allows it.
+    /// The returned guard pops the span when dropped, so nesting tracks call
+    /// depth automatically via normal Rust scoping.
+    pub fn span(&self, phase: &'static str, name: impl Into<String>, fields: &[Field]) -> SpanGuard {
+        let name = name.into();
+        let label = format!("{phase}::{name}");
+        if self.config.allows(Level::Debug, phase) {
+            let depth = ACTIVE_SPANS.with(|s| s.borrow().len());
+            let mut line = format!("{}{label}", "  ".repeat(depth));
+            for f in fields {
+                let _ = write!(line, " {}={}", f.key, f.value);
+            }
+            eprintln!("{line}");
+        }
+        ACTIVE_SPANS.with(|s| s.borrow_mut().push(label));
+        SpanGuard(())
+    }
+
+    /// Emits a one-off event inside whatever span is currently active.
+    pub fn event(&self, phase: &'static str, level: Level, message: impl std::fmt::Display) {
+        if self.config.allows(level, phase) {
+            let depth = ACTIVE_SPANS.with(|s| s.borrow().len());
+            eprintln!("{}{phase}: {message}", "  ".repeat(depth));
+        }
+    }
+
+    /// Installs a panic hook that dumps the active span stack (innermost
+    /// first) before handing off to whatever hook was previously installed,
+    /// so a panic mid-pass shows exactly which spans were entered to get
+    /// there.
+    pub fn install_panic_hook() {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            ACTIVE_SPANS.with(|s| {
+                let stack = s.borrow();
+                if !stack.is_empty() {
+                    eprintln!("panic while in:");
+                    for frame in stack.iter().rev() {
+                        eprintln!("  {frame}");
+                    }
+                }
+            });
+            previous(info);
+        }));
+    }
+}
+
+/// Pops its span from the active stack on drop, so nesting tracks the
+/// guard's scope exactly.
+pub struct SpanGuard(());
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        ACTIVE_SPANS.with(|s| {
+            s.borrow_mut().pop();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_flag_enables_info_level_tracing_of_every_phase() {
+        let config = Config::from_flag(None);
+        assert!(config.enabled);
+        assert_eq!(config.level, Level::Info);
+        assert!(config.phases.is_none());
+    }
+
+    #[test]
+    fn level_only_flag_sets_the_level_and_traces_every_phase() {
+        let config = Config::from_flag(Some("debug"));
+        assert!(config.enabled);
+        assert_eq!(config.level, Level::Debug);
+        assert!(config.phases.is_none());
+    }
+
+    #[test]
+    fn level_and_phases_flag_restricts_emission_to_the_named_phases() {
+        let config = Config::from_flag(Some("debug:typeck,comptime"));
+        assert_eq!(config.level, Level::Debug);
+        let phases = config.phases.expect("a phase filter was given");
+        assert!(phases.contains("typeck"));
+        assert!(phases.contains("comptime"));
+        assert!(!phases.contains("parser"));
+    }
+
+    #[test]
+    fn an_unrecognized_level_falls_back_to_info() {
+        let config = Config::from_flag(Some("not-a-level"));
+        assert_eq!(config.level, Level::Info);
+    }
+
+    #[test]
+    fn allows_is_false_when_disabled_regardless_of_level_or_phase() {
+        let config = Config::default();
+        assert!(!config.allows(Level::Error, "typeck"));
+    }
+
+    #[test]
+    fn allows_gates_on_level_ordering() {
+        let config = Config::from_flag(Some("info"));
+        assert!(config.allows(Level::Info, "typeck"));
+        assert!(config.allows(Level::Error, "typeck"));
+        assert!(!config.allows(Level::Debug, "typeck"));
+    }
+
+    #[test]
+    fn allows_gates_on_phase_filter() {
+        let config = Config::from_flag(Some("debug:typeck"));
+        assert!(config.allows(Level::Debug, "typeck"));
+        assert!(!config.allows(Level::Debug, "comptime"));
+    }
+}
@@ -0,0 +1,133 @@
+//! Type-checking rules that don't fit neatly under a single pass name yet.
+
+use crate::diagnostics::{Diagnostic, DiagnosticBag, Span};
+use crate::hir::Ty;
+use crate::trace::{self, Tracer};
+
+/// Type-checks a lowered `expr?` (see [`crate::hir::Expr::Try`]).
+///
+/// `operand_ty` is the type of the expression `?` was applied to, and
+/// `enclosing_return` is the declared return type of the function the
+/// expression appears in. Returns the type the `?` expression evaluates to
+/// (the unwrapped success value) so the caller can keep type-checking the
+/// rest of the enclosing expression even after reporting an error.
+pub fn check_try(
+    operand_ty: &Ty,
+    enclosing_return: &Ty,
+    span: Span,
+    bag: &mut DiagnosticBag,
+    tracer: &Tracer,
+) -> Ty {
+    let _span_guard = tracer.span(
+        "typeck",
+        "check_try",
+        &[
+            trace::field("operand_ty", format!("{operand_ty:?}")),
+            trace::field("enclosing_return", format!("{enclosing_return:?}")),
+        ],
+    );
+    let Ty::Result { ok, err } = operand_ty else {
+        bag.push(Diagnostic::error(
+            span,
+            format!("`?` can only be applied to a `Result ! E` value, found `{operand_ty:?}`"),
+        ));
+        return Ty::Unit;
+    };
+
+    match enclosing_return {
+        Ty::Result { err: ret_err, .. } if ret_err == err => *ok.clone(),
+        Ty::Result { err: ret_err, .. } => {
+            // No implicit error-conversion trait exists yet, so the error
+            // types must match exactly; once the crate grows one, this is
+            // where an implicit `.into()` would be inserted instead.
+            bag.push(Diagnostic::error(
+                span,
+                format!(
+                    "`?` propagates an error of type `{err:?}`, but the enclosing function returns `Result ! {ret_err:?}`"
+                ),
+            ));
+            *ok.clone()
+        }
+        other => {
+            bag.push(Diagnostic::error(
+                span,
+                format!(
+                    "`?` can only be used in a function returning `Result ! E`, but the enclosing function returns `{other:?}`"
+                ),
+            ));
+            *ok.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span() -> Span {
+        Span::new(0, 1)
+    }
+
+    fn result_ty(ok: Ty, err: Ty) -> Ty {
+        Ty::Result {
+            ok: Box::new(ok),
+            err: Box::new(err),
+        }
+    }
+
+    #[test]
+    fn matching_error_types_unwrap_to_the_ok_type_with_no_diagnostic() {
+        let mut bag = DiagnosticBag::new();
+        let ty = check_try(
+            &result_ty(Ty::I32, Ty::Str),
+            &result_ty(Ty::Bool, Ty::Str),
+            span(),
+            &mut bag,
+            &Tracer::disabled(),
+        );
+        assert!(!bag.has_errors(), "matching error types must not be rejected");
+        assert_eq!(ty, Ty::I32);
+    }
+
+    #[test]
+    fn mismatched_error_types_are_rejected() {
+        let mut bag = DiagnosticBag::new();
+        check_try(
+            &result_ty(Ty::I32, Ty::Str),
+            &result_ty(Ty::Bool, Ty::I32),
+            span(),
+            &mut bag,
+            &Tracer::disabled(),
+        );
+        assert!(
+            bag.has_errors(),
+            "a `?` propagating a different error type than the enclosing function returns must be rejected"
+        );
+    }
+
+    #[test]
+    fn non_result_enclosing_return_is_rejected() {
+        let mut bag = DiagnosticBag::new();
+        check_try(
+            &result_ty(Ty::I32, Ty::Str),
+            &Ty::Unit,
+            span(),
+            &mut bag,
+            &Tracer::disabled(),
+        );
+        assert!(
+            bag.has_errors(),
+            "`?` inside a function that doesn't return `Result ! E` must be rejected"
+        );
+    }
+
+    #[test]
+    fn non_result_operand_is_rejected() {
+        let mut bag = DiagnosticBag::new();
+        check_try(&Ty::I32, &result_ty(Ty::I32, Ty::Str), span(), &mut bag, &Tracer::disabled());
+        assert!(
+            bag.has_errors(),
+            "`?` applied to a non-`Result` value must be rejected"
+        );
+    }
+}